@@ -19,21 +19,168 @@ impl ScriptConfig {
     }
 }
 
+/// Tracks an app's releases directly on GitHub instead of hand-rolled scripts:
+/// the remote version comes from the latest release's tag, and `update`
+/// downloads and atomically installs the matching asset.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GithubSource {
+    /// `owner/repo` slug, as passed to the GitHub releases API.
+    pub github: String,
+    /// Glob (`*` wildcards) used to pick an asset when a release ships more than
+    /// one build per platform. Falls back to matching the current OS/arch when omitted.
+    #[serde(default)]
+    pub asset: Option<String>,
+    /// Where the downloaded binary is installed. Defaults to `~/.local/bin/<name>`.
+    #[serde(default)]
+    pub install_path: Option<String>,
+}
+
+impl GithubSource {
+    /// Resolves `install_path`, defaulting to `~/.local/bin/<app_name>`.
+    pub fn resolved_install_path(&self, app_name: &str) -> anyhow::Result<String> {
+        if let Some(path) = &self.install_path {
+            return Ok(path.clone());
+        }
+
+        let home = std::env::var("HOME")
+            .map_err(|_| anyhow::anyhow!("HOME environment variable not set"))?;
+        Ok(format!("{}/.local/bin/{}", home, app_name))
+    }
+}
+
+/// A well-known package manager that `provider` can expand into local/remote/update
+/// commands, so apps managed by a system package manager don't need hand-rolled shell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Homebrew,
+    Cargo,
+}
+
+impl Provider {
+    /// Picks the right brew binary for the machine's architecture, mirroring how
+    /// topgrade distinguishes Apple Silicon's `/opt/homebrew` prefix from Intel's
+    /// `/usr/local`, and falls back to `brew` on `$PATH` (e.g. Linuxbrew).
+    fn brew_binary() -> &'static str {
+        if std::env::consts::ARCH == "aarch64" && Path::new("/opt/homebrew/bin/brew").exists() {
+            "/opt/homebrew/bin/brew"
+        } else if Path::new("/usr/local/bin/brew").exists() {
+            "/usr/local/bin/brew"
+        } else {
+            "brew"
+        }
+    }
+
+    pub fn default_local_command(&self, app_name: &str) -> String {
+        match self {
+            Self::Homebrew => format!(
+                "{} list --versions {} | awk '{{print $NF}}'",
+                Self::brew_binary(),
+                app_name
+            ),
+            Self::Cargo => format!(
+                "cargo install --list | grep -E '^{} v' | sed -E 's/^{} v([^:]+):.*/\\1/'",
+                app_name, app_name
+            ),
+        }
+    }
+
+    pub fn default_remote_command(&self, app_name: &str) -> String {
+        match self {
+            Self::Homebrew => format!(
+                "{} info --json=v2 {} | jq -r '.formulae[0].versions.stable'",
+                Self::brew_binary(),
+                app_name
+            ),
+            Self::Cargo => format!(
+                "curl -s https://crates.io/api/v1/crates/{} | jq -r '.crate.max_stable_version'",
+                app_name
+            ),
+        }
+    }
+
+    pub fn default_update_command(&self, app_name: &str) -> String {
+        match self {
+            Self::Homebrew => format!("{} upgrade {}", Self::brew_binary(), app_name),
+            Self::Cargo => format!("cargo install {} --force", app_name),
+        }
+    }
+
+    /// Providers publish proper semver, so `compare = "semver"` is implied
+    /// unless the app overrides it explicitly.
+    pub fn default_compare_mode(&self) -> CompareMode {
+        CompareMode::Semver
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct App {
     pub name: String,
     pub description: Option<String>,
-    pub local: ScriptConfig,
-    pub remote: ScriptConfig,
-    pub update: ScriptConfig,
+    /// Explicit scripts. A `provider` fills in whichever of these are omitted;
+    /// `source` replaces all three outright.
+    #[serde(default)]
+    pub local: Option<ScriptConfig>,
+    #[serde(default)]
+    pub remote: Option<ScriptConfig>,
+    #[serde(default)]
+    pub update: Option<ScriptConfig>,
+    /// A built-in app source (currently just GitHub releases) in place of scripts.
+    #[serde(default)]
+    pub source: Option<GithubSource>,
+    /// A well-known package manager whose default commands back any of
+    /// local/remote/update the app doesn't override explicitly.
+    #[serde(default)]
+    pub provider: Option<Provider>,
+    /// Explicit override; `None` falls back to the provider's default (semver)
+    /// or `CompareMode::String` when there is no provider.
     #[serde(rename = "compare", default)]
-    pub compare_mode: CompareMode,
+    pub compare_mode: Option<CompareMode>,
+}
+
+impl App {
+    pub fn effective_compare_mode(&self) -> CompareMode {
+        self.compare_mode.unwrap_or_else(|| {
+            self.provider
+                .map(|p| p.default_compare_mode())
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn effective_local_command(&self) -> Option<String> {
+        if let Some(script) = &self.local {
+            return Some(script.as_command().to_string());
+        }
+        self.provider.map(|p| p.default_local_command(&self.name))
+    }
+
+    pub fn effective_remote_command(&self) -> Option<String> {
+        if let Some(script) = &self.remote {
+            return Some(script.as_command().to_string());
+        }
+        self.provider.map(|p| p.default_remote_command(&self.name))
+    }
+
+    pub fn effective_update_command(&self) -> Option<String> {
+        if let Some(script) = &self.update {
+            return Some(script.as_command().to_string());
+        }
+        self.provider.map(|p| p.default_update_command(&self.name))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     #[serde(rename = "app")]
     pub apps: Vec<App>,
+    /// How long a cached remote version check stays valid before `check`/`update`
+    /// re-run the remote script. Overridden per-invocation by `--refresh`.
+    #[serde(default = "default_check_interval_hours")]
+    pub check_interval_hours: u64,
+}
+
+fn default_check_interval_hours() -> u64 {
+    24
 }
 
 impl Config {
@@ -48,9 +195,21 @@ impl Config {
             if app.name.is_empty() {
                 return Err(anyhow::anyhow!("App name must not be empty"));
             }
-            validate_script_config(&app.local)?;
-            validate_script_config(&app.remote)?;
-            validate_script_config(&app.update)?;
+
+            if app.source.is_some() {
+                continue;
+            }
+
+            for script in [&app.local, &app.remote, &app.update].into_iter().flatten() {
+                validate_script_config(script)?;
+            }
+
+            if app.provider.is_none() && (app.local.is_none() || app.remote.is_none() || app.update.is_none()) {
+                return Err(anyhow::anyhow!(
+                    "app '{}' must define [app.source], a provider, or local/remote/update scripts",
+                    app.name
+                ));
+            }
         }
         Ok(())
     }
@@ -105,8 +264,11 @@ file = "/tmp/update.sh"
         let config: Config = toml::from_str(toml_str).unwrap();
         assert_eq!(config.apps.len(), 1);
         assert_eq!(config.apps[0].name, "dust");
-        assert_eq!(config.apps[0].compare_mode, CompareMode::String);
-        assert_eq!(config.apps[0].local.as_command(), "/tmp/local.sh");
+        assert_eq!(config.apps[0].effective_compare_mode(), CompareMode::String);
+        assert_eq!(
+            config.apps[0].local.as_ref().unwrap().as_command(),
+            "/tmp/local.sh"
+        );
     }
 
     #[test]
@@ -125,10 +287,115 @@ inline = "curl -s https://example.com/version"
 inline = "brew upgrade myapp"
 "#;
         let config: Config = toml::from_str(toml_str).unwrap();
-        assert_eq!(config.apps[0].local.as_command(), "myapp --version");
         assert_eq!(
-            config.apps[0].remote.as_command(),
+            config.apps[0].local.as_ref().unwrap().as_command(),
+            "myapp --version"
+        );
+        assert_eq!(
+            config.apps[0].remote.as_ref().unwrap().as_command(),
             "curl -s https://example.com/version"
         );
     }
+
+    #[test]
+    fn test_parse_github_source() {
+        let toml_str = r#"
+[[app]]
+name = "bat"
+compare = "semver"
+
+[app.source]
+github = "sharkdp/bat"
+asset = "bat-*-x86_64-unknown-linux-gnu.tar.gz"
+install_path = "/usr/local/bin/bat"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.apps.len(), 1);
+        assert!(config.apps[0].local.is_none());
+        let source = config.apps[0].source.as_ref().unwrap();
+        assert_eq!(source.github, "sharkdp/bat");
+        assert_eq!(
+            source.resolved_install_path("bat").unwrap(),
+            "/usr/local/bin/bat"
+        );
+    }
+
+    #[test]
+    fn test_github_source_defaults_install_path() {
+        let source = GithubSource {
+            github: "sharkdp/bat".to_string(),
+            asset: None,
+            install_path: None,
+        };
+
+        let previous_home = std::env::var("HOME").ok();
+        // SAFETY: `set_var`/`remove_var` are only unsound if another thread
+        // concurrently reads the environment; this is the only test in the
+        // crate that touches HOME, and it's restored before returning.
+        unsafe {
+            std::env::set_var("HOME", "/home/tester");
+        }
+        let result = source.resolved_install_path("bat");
+        unsafe {
+            match &previous_home {
+                Some(home) => std::env::set_var("HOME", home),
+                None => std::env::remove_var("HOME"),
+            }
+        }
+
+        assert_eq!(result.unwrap(), "/home/tester/.local/bin/bat");
+    }
+
+    #[test]
+    fn test_validate_requires_source_or_scripts() {
+        let config = Config {
+            apps: vec![App {
+                name: "incomplete".to_string(),
+                description: None,
+                local: None,
+                remote: None,
+                update: None,
+                source: None,
+                provider: None,
+                compare_mode: None,
+            }],
+            check_interval_hours: 24,
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_provider_fills_in_missing_scripts() {
+        let toml_str = r#"
+[[app]]
+name = "dust"
+provider = "homebrew"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let app = &config.apps[0];
+        assert!(app.local.is_none());
+        assert_eq!(app.effective_compare_mode(), CompareMode::Semver);
+        assert!(app.effective_local_command().unwrap().contains("brew"));
+        assert!(app.effective_update_command().unwrap().contains("upgrade dust"));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_explicit_script_overrides_provider_default() {
+        let toml_str = r#"
+[[app]]
+name = "dust"
+provider = "cargo"
+
+[app.update]
+inline = "cargo install dust --force --locked"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let app = &config.apps[0];
+        assert_eq!(
+            app.effective_update_command().unwrap(),
+            "cargo install dust --force --locked"
+        );
+        assert!(app.effective_local_command().unwrap().contains("cargo install --list"));
+    }
 }
@@ -6,8 +6,17 @@ pub struct ScriptResult {
 }
 
 pub fn run_script(script_path: &str) -> std::io::Result<ScriptResult> {
-    let output = Command::new("sh")
-        .arg("-c")
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+
+    let output = command
         .arg(script_path)
         .stdout(Stdio::piped())
         .stderr(Stdio::inherit())
@@ -2,13 +2,18 @@ use clap::{Parser, Subcommand};
 use semver::Version;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 mod config;
+mod progress;
 mod self_update;
+mod state;
 mod version;
 
-use crate::config::Config;
-use crate::version::CompareMode;
+use crate::config::{App, Config};
+use crate::progress::Progress;
+use crate::state::CheckState;
 use uppies::{run_script, trim_version};
 
 #[derive(Parser)]
@@ -24,7 +29,14 @@ enum Commands {
     /// List all registered apps
     List,
     /// Check local vs remote versions
-    Check,
+    Check {
+        /// Bypass the cached remote version and re-run every remote script
+        #[arg(long)]
+        refresh: bool,
+        /// Number of apps to check concurrently (defaults to the number of CPUs)
+        #[arg(long)]
+        jobs: Option<usize>,
+    },
     /// Update app(s) if versions differ
     Update {
         /// Name of the app to update
@@ -32,6 +44,12 @@ enum Commands {
         /// Bypass version check
         #[arg(long)]
         force: bool,
+        /// Bypass the cached remote version and re-run every remote script
+        #[arg(long)]
+        refresh: bool,
+        /// Number of apps to check concurrently (defaults to the number of CPUs)
+        #[arg(long)]
+        jobs: Option<usize>,
     },
     /// Update uppies itself
     SelfUpdate,
@@ -47,14 +65,184 @@ fn get_config_path() -> Result<PathBuf, String> {
     Ok(path)
 }
 
+fn get_check_state_path() -> Result<PathBuf, String> {
+    let home =
+        std::env::var("HOME").map_err(|_| "HOME environment variable not set".to_string())?;
+    let mut path = PathBuf::from(home);
+    path.push(".local/share/uppies/check-state.json");
+    Ok(path)
+}
+
+/// Resolves an app's local (installed) version, deriving it from the
+/// installed binary for `source`-based apps or running the `local` script otherwise.
+fn resolve_local_version(app: &App) -> Option<String> {
+    match &app.source {
+        Some(source) => {
+            let install_path = match source.resolved_install_path(&app.name) {
+                Ok(path) => path,
+                Err(e) => {
+                    eprintln!("{}: {}", app.name, e);
+                    return None;
+                }
+            };
+            match run_script(&format!("{} --version", install_path)) {
+                Ok(res) if res.exit_code == 0 => Some(trim_version(&res.stdout).to_string()),
+                _ => {
+                    eprintln!("{}: local version check failed", app.name);
+                    None
+                }
+            }
+        }
+        None => {
+            let command = app.effective_local_command()?;
+            match run_script(&command) {
+                Ok(res) if res.exit_code == 0 => Some(trim_version(&res.stdout).to_string()),
+                _ => {
+                    eprintln!("{}: local version script failed", app.name);
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Resolves an app's remote version, reusing a cached value from `state` when
+/// it's younger than `interval_hours` (unless `refresh` forces a fresh fetch).
+/// Any freshly-fetched version is recorded back into `state`. For `source`-based
+/// apps the remote version is the latest GitHub release's tag.
+/// The lock on `state` is held only to read and record the cache, not while
+/// the remote script or GitHub request runs.
+fn resolve_remote_version(
+    state: &Mutex<CheckState>,
+    app: &App,
+    interval_hours: u64,
+    refresh: bool,
+) -> Option<String> {
+    if !refresh
+        && let Some(cached) = state
+            .lock()
+            .unwrap()
+            .fresh_remote_version(&app.name, interval_hours)
+            .map(str::to_string)
+    {
+        return Some(cached);
+    }
+
+    let remote_v = match &app.source {
+        Some(source) => match self_update::fetch_latest_release(&source.github) {
+            Ok(release) => trim_version(&release.version).to_string(),
+            Err(e) => {
+                eprintln!("{}: failed to fetch latest release: {}", app.name, e);
+                return None;
+            }
+        },
+        None => {
+            let command = app.effective_remote_command()?;
+            match run_script(&command) {
+                Ok(res) if res.exit_code == 0 => trim_version(&res.stdout).to_string(),
+                _ => {
+                    eprintln!("{}: remote version script failed", app.name);
+                    return None;
+                }
+            }
+        }
+    };
+
+    state.lock().unwrap().record(&app.name, &remote_v);
+    Some(remote_v)
+}
+
+/// Returns whether a just-applied update actually moved the local version,
+/// given the version captured before running it and the version observed
+/// right after. An update whose script exits 0 but leaves the version
+/// unchanged counts as not advanced, and so does one where the local version
+/// couldn't be determined before or after; the two cases are not told apart.
+fn version_advanced(baseline: &Option<String>, after: &Option<String>) -> bool {
+    match (baseline, after) {
+        (Some(before), Some(after)) => after != before,
+        _ => false,
+    }
+}
+
+/// Runs an app's update: downloads and installs the matching GitHub release
+/// asset for `source`-based apps, or runs the configured `update` script.
+fn run_update(app: &App) -> anyhow::Result<()> {
+    if let Some(source) = &app.source {
+        let release = self_update::fetch_latest_release(&source.github)?;
+        let asset = self_update::find_asset(&release.assets, source.asset.as_deref())?;
+        let install_path = source.resolved_install_path(&app.name)?;
+        return self_update::install_release_asset(&asset.browser_download_url, &app.name, &install_path);
+    }
+
+    let Some(command) = app.effective_update_command() else {
+        anyhow::bail!("app has no update source configured");
+    };
+    let res = run_script(&command)?;
+    if res.exit_code != 0 {
+        anyhow::bail!("update script exited with code {}", res.exit_code);
+    }
+    Ok(())
+}
+
+/// Outcome of the concurrent version-check phase of `update`, carried forward
+/// into the sequential apply phase so the actual install work never races.
+enum UpdateCheck {
+    Forced,
+    UpToDate { local_v: String },
+    NeedsUpdate { local_v: String, remote_v: String },
+}
+
+fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Runs `f` over `items` using up to `jobs` concurrent threads — a small
+/// work-stealing pool over `std::thread::scope` — and returns results in the
+/// same order as `items`, regardless of which finishes first, so output stays
+/// deterministic even though the work doesn't.
+fn run_concurrently<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(usize, &T) -> R + Sync,
+{
+    let jobs = jobs.max(1).min(items.len().max(1));
+    let results: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+    let next_index = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next_index.fetch_add(1, Ordering::SeqCst);
+                if i >= items.len() {
+                    break;
+                }
+                let result = f(i, &items[i]);
+                *results[i].lock().unwrap() = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|cell| cell.into_inner().unwrap().expect("every index is processed exactly once"))
+        .collect()
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if let Ok(exe_path) = std::env::current_exe() {
+        self_update::cleanup_stale_self_update(&exe_path.to_string_lossy());
+    }
+
     let cli = Cli::parse();
 
     match cli.command {
         Commands::List => {
             let config_path = get_config_path()?;
             let config =
-                Config::load_from_file(config_path.to_str().ok_or("Invalid config path")?)?;
+                Config::load_from_file(&config_path)?;
             if config.apps.is_empty() {
                 println!("No apps registered");
             } else {
@@ -67,48 +255,51 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
-        Commands::Check => {
+        Commands::Check { refresh, jobs } => {
             let config_path = get_config_path()?;
             let config =
-                Config::load_from_file(config_path.to_str().ok_or("Invalid config path")?)?;
+                Config::load_from_file(&config_path)?;
             config.validate()?;
 
-            for app in config.apps {
-                let local_res = match run_script(&app.local.script) {
-                    Ok(res) if res.exit_code == 0 => res.stdout,
-                    _ => {
-                        eprintln!("{}: local version script failed", app.name);
-                        continue;
-                    }
+            let state_path = get_check_state_path()?;
+            let state = Mutex::new(CheckState::load_from_file(&state_path));
+
+            let app_names: Vec<String> = config.apps.iter().map(|a| a.name.clone()).collect();
+            let progress = Progress::new(&app_names);
+
+            let outcomes = run_concurrently(&config.apps, jobs.unwrap_or_else(default_jobs), |i, app| {
+                progress.update(i, &app.name, "checking...");
+
+                let Some(local_ver) = resolve_local_version(app) else {
+                    progress.finish(i, &app.name, "local version check failed");
+                    return None;
                 };
-                let remote_res = match run_script(&app.remote.script) {
-                    Ok(res) if res.exit_code == 0 => res.stdout,
-                    _ => {
-                        eprintln!("{}: remote version script failed", app.name);
-                        continue;
-                    }
+                let remote_ver =
+                    resolve_remote_version(&state, app, config.check_interval_hours, refresh);
+                let Some(remote_ver) = remote_ver else {
+                    progress.finish(i, &app.name, "remote version check failed");
+                    return None;
                 };
 
-                let local_ver = trim_version(&local_res);
-                let remote_ver = trim_version(&remote_res);
-
-                let needs_update = match app.compare_mode {
-                    CompareMode::String => local_ver != remote_ver,
-                    CompareMode::Semver => {
-                        let local_sem = Version::parse(local_ver.trim_start_matches('v'));
-                        let remote_sem = Version::parse(remote_ver.trim_start_matches('v'));
-                        match (local_sem, remote_sem) {
-                            (Ok(l), Ok(r)) => l < r,
-                            _ => {
-                                eprintln!(
-                                    "{}: failed to parse semver (local: {}, remote: {})",
-                                    app.name, local_ver, remote_ver
-                                );
-                                continue;
-                            }
-                        }
-                    }
+                progress.finish(i, &app.name, "done");
+                Some((local_ver, remote_ver))
+            });
+
+            for (app, outcome) in config.apps.iter().zip(outcomes) {
+                let Some((local_ver, remote_ver)) = outcome else {
+                    continue;
                 };
+                let local_ver = local_ver.as_str();
+                let remote_ver = remote_ver.as_str();
+
+                let needs_update =
+                    match version::needs_update(app.effective_compare_mode(), local_ver, remote_ver) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            eprintln!("{}: {}", app.name, e);
+                            continue;
+                        }
+                    };
 
                 if needs_update {
                     println!(
@@ -119,82 +310,123 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("{:<20} {:<15} (up to date)", app.name, local_ver);
                 }
             }
+
+            state.into_inner().unwrap().save_to_file(&state_path)?;
         }
         Commands::Update {
             app: app_name,
             force,
+            refresh,
+            jobs,
         } => {
             let config_path = get_config_path()?;
             let config =
-                Config::load_from_file(config_path.to_str().ok_or("Invalid config path")?)?;
+                Config::load_from_file(&config_path)?;
             config.validate()?;
 
-            for app in config.apps {
-                if let Some(ref target) = app_name
-                    && &app.name != target
-                {
-                    continue;
+            let state_path = get_check_state_path()?;
+            let state = Mutex::new(CheckState::load_from_file(&state_path));
+
+            let targets: Vec<&App> = config
+                .apps
+                .iter()
+                .filter(|app| {
+                    if let Some(ref target) = app_name
+                        && &app.name != target
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let target_names: Vec<String> = targets.iter().map(|a| a.name.clone()).collect();
+            let progress = Progress::new(&target_names);
+
+            // Version checks run concurrently; the actual update is applied
+            // sequentially below since it mutates system/package-manager state.
+            let checks = run_concurrently(&targets, jobs.unwrap_or_else(default_jobs), |i, app| {
+                if force {
+                    progress.finish(i, &app.name, "forced");
+                    return Some(UpdateCheck::Forced);
                 }
 
-                let mut should_update = force;
+                progress.update(i, &app.name, "checking...");
+                let Some(local_v) = resolve_local_version(app) else {
+                    progress.finish(i, &app.name, "local version check failed");
+                    return None;
+                };
+                let remote_v =
+                    resolve_remote_version(&state, app, config.check_interval_hours, refresh);
+                let Some(remote_v) = remote_v else {
+                    progress.finish(i, &app.name, "remote version check failed");
+                    return None;
+                };
 
-                if !force {
-                    let local_res = match run_script(&app.local.script) {
-                        Ok(res) if res.exit_code == 0 => res.stdout,
-                        _ => {
-                            eprintln!("{}: local version script failed", app.name);
-                            continue;
-                        }
-                    };
-                    let remote_res = match run_script(&app.remote.script) {
-                        Ok(res) if res.exit_code == 0 => res.stdout,
-                        _ => {
-                            eprintln!("{}: remote version script failed", app.name);
-                            continue;
+                let needs_update =
+                    match version::needs_update(app.effective_compare_mode(), &local_v, &remote_v) {
+                        Ok(v) => v,
+                        Err(_) => {
+                            progress.finish(i, &app.name, "failed to parse semver");
+                            return None;
                         }
                     };
 
-                    let local_v = trim_version(&local_res);
-                    let remote_v = trim_version(&remote_res);
-
-                    let needs_update = match app.compare_mode {
-                        CompareMode::String => local_v != remote_v,
-                        CompareMode::Semver => {
-                            let local_sem = Version::parse(local_v.trim_start_matches('v'));
-                            let remote_sem = Version::parse(remote_v.trim_start_matches('v'));
-                            match (local_sem, remote_sem) {
-                                (Ok(l), Ok(r)) => l < r,
-                                _ => {
-                                    eprintln!(
-                                        "{}: failed to parse semver (local: {}, remote: {})",
-                                        app.name, local_v, remote_v
-                                    );
-                                    continue;
-                                }
-                            }
-                        }
-                    };
+                if needs_update {
+                    progress.finish(i, &app.name, "update available");
+                    Some(UpdateCheck::NeedsUpdate { local_v, remote_v })
+                } else {
+                    progress.finish(i, &app.name, "up to date");
+                    Some(UpdateCheck::UpToDate { local_v })
+                }
+            });
+
+            for (app, check) in targets.into_iter().zip(checks) {
+                let mut should_update = false;
+                let mut known_local_v: Option<String> = None;
 
-                    if needs_update {
+                match check {
+                    None => continue,
+                    Some(UpdateCheck::Forced) => {
                         should_update = true;
-                        println!("{}: updating {} → {}", app.name, local_v, remote_v);
-                    } else {
+                    }
+                    Some(UpdateCheck::UpToDate { local_v }) => {
                         println!("{}: already up to date ({})", app.name, local_v);
                     }
+                    Some(UpdateCheck::NeedsUpdate { local_v, remote_v }) => {
+                        should_update = true;
+                        println!("{}: updating {} → {}", app.name, local_v, remote_v);
+                        known_local_v = Some(local_v);
+                    }
                 }
 
                 if should_update {
-                    println!("{}: running update script...", app.name);
-                    match run_script(&app.update.script) {
-                        Ok(res) if res.exit_code == 0 => {
-                            println!("{}: update complete", app.name);
+                    // Capture a baseline so an update that exits cleanly but doesn't
+                    // move the local version is reported separately from one that does.
+                    let baseline_v = known_local_v.or_else(|| resolve_local_version(app));
+
+                    println!("{}: running update...", app.name);
+                    match run_update(app) {
+                        Ok(()) => {
+                            let advanced = version_advanced(&baseline_v, &resolve_local_version(app));
+
+                            if advanced {
+                                println!("{}: update complete", app.name);
+                            } else {
+                                eprintln!(
+                                    "{}: update completed but local version did not advance",
+                                    app.name
+                                );
+                            }
                         }
-                        _ => {
-                            eprintln!("{}: update script failed", app.name);
+                        Err(e) => {
+                            eprintln!("{}: update failed: {}", app.name, e);
                         }
                     }
                 }
             }
+
+            state.into_inner().unwrap().save_to_file(&state_path)?;
         }
         Commands::SelfUpdate => {
             let repo =
@@ -223,13 +455,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             println!("\nDownloading uppies {}...", release.version);
 
-            let platform = self_update::Platform::current();
+            let platform = self_update::Platform::current()?;
             let asset_name = platform.asset_name();
+            let sig_name = format!("{}.sig", asset_name);
             let asset = release
                 .assets
-                .into_iter()
+                .iter()
                 .find(|a| a.name == asset_name)
                 .ok_or_else(|| format!("No asset found for platform {:?}", platform))?;
+            let sig_asset = release
+                .assets
+                .iter()
+                .find(|a| a.name == sig_name)
+                .ok_or_else(|| format!("No signature asset found for {}", asset_name))?;
 
             // Temp dir
             let tmp_dir = format!(
@@ -240,10 +478,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             );
             fs::create_dir_all(&tmp_dir)?;
 
-            self_update::download_and_extract(&asset.browser_download_url, &tmp_dir)?;
+            let archive_ext = if asset_name.ends_with(".zip") { "zip" } else { "tar.gz" };
+            let archive_path = format!("{}/uppies-download.{}", tmp_dir, archive_ext);
+            self_update::download_file(&asset.browser_download_url, &archive_path)?;
+            self_update::download_file(
+                &sig_asset.browser_download_url,
+                &format!("{}.sig", archive_path),
+            )?;
+
+            let archive_bytes = fs::read(&archive_path)?;
+            let signature = fs::read_to_string(format!("{}.sig", archive_path))?;
+
+            if let Err(e) = self_update::verify_archive(&archive_bytes, &signature) {
+                let _ = fs::remove_dir_all(&tmp_dir);
+                return Err(format!("Signature verification failed, aborting update: {}", e).into());
+            }
+            println!("Signature verified.");
+
+            if let Err(e) = self_update::extract_archive(&archive_path, &tmp_dir) {
+                let _ = fs::remove_dir_all(&tmp_dir);
+                return Err(e.into());
+            }
 
             let exe_path = std::env::current_exe()?;
-            let new_binary = format!("{}/uppies", tmp_dir);
+            let new_binary = format!("{}/uppies{}", tmp_dir, std::env::consts::EXE_SUFFIX);
 
             println!("Installing...");
             self_update::replace_binary(&new_binary, exe_path.to_str().ok_or("Invalid exe path")?)?;
@@ -258,3 +516,31 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_advanced_when_versions_differ() {
+        assert!(version_advanced(
+            &Some("1.0.0".to_string()),
+            &Some("1.1.0".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_version_not_advanced_when_unchanged() {
+        assert!(!version_advanced(
+            &Some("1.0.0".to_string()),
+            &Some("1.0.0".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_version_not_advanced_when_either_side_unknown() {
+        assert!(!version_advanced(&None, &Some("1.0.0".to_string())));
+        assert!(!version_advanced(&Some("1.0.0".to_string()), &None));
+        assert!(!version_advanced(&None, &None));
+    }
+}
@@ -0,0 +1,51 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::time::Duration;
+
+/// Per-app progress display for `check`/`update` runs. Renders a live
+/// multi-spinner when stdout is a TTY; otherwise falls back to plain lines
+/// (no live redraw, so output stays sane when piped or run in CI).
+pub enum Progress {
+    Interactive(Vec<ProgressBar>),
+    Plain,
+}
+
+impl Progress {
+    pub fn new(app_names: &[String]) -> Self {
+        if !std::io::stdout().is_terminal() {
+            return Self::Plain;
+        }
+
+        let multi = MultiProgress::new();
+        let style = ProgressStyle::with_template("{spinner:.green} {prefix:<20} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_spinner());
+
+        let bars = app_names
+            .iter()
+            .map(|name| {
+                let bar = multi.add(ProgressBar::new_spinner());
+                bar.set_style(style.clone());
+                bar.set_prefix(name.clone());
+                bar.set_message("waiting...");
+                bar.enable_steady_tick(Duration::from_millis(100));
+                bar
+            })
+            .collect();
+
+        Self::Interactive(bars)
+    }
+
+    pub fn update(&self, index: usize, app_name: &str, message: &str) {
+        match self {
+            Self::Interactive(bars) => bars[index].set_message(message.to_string()),
+            Self::Plain => println!("{}: {}", app_name, message),
+        }
+    }
+
+    pub fn finish(&self, index: usize, app_name: &str, message: &str) {
+        match self {
+            Self::Interactive(bars) => bars[index].finish_with_message(message.to_string()),
+            Self::Plain => println!("{}: {}", app_name, message),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Per-app cache of the last-fetched remote version, persisted next to the
+/// config so `check`/`update` don't re-run every remote script on every run.
+/// Unknown or missing apps are simply treated as uncached, so schema drift
+/// (apps renamed, config keys added) never hard-fails a load.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CheckState {
+    #[serde(default)]
+    apps: HashMap<String, CachedRemote>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedRemote {
+    remote_version: String,
+    checked_at_unix: u64,
+}
+
+impl CheckState {
+    /// Loads the state file, tolerating a missing or unparsable file by
+    /// falling back to an empty (all-uncached) state.
+    pub fn load_from_file(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Returns the cached remote version for `app_name` if it was fetched
+    /// within `max_age_hours`, or `None` if absent or stale.
+    pub fn fresh_remote_version(&self, app_name: &str, max_age_hours: u64) -> Option<&str> {
+        let cached = self.apps.get(app_name)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let age_secs = now.saturating_sub(cached.checked_at_unix);
+        (age_secs < max_age_hours.saturating_mul(3600)).then_some(cached.remote_version.as_str())
+    }
+
+    /// Records a freshly-fetched remote version, timestamped now.
+    pub fn record(&mut self, app_name: &str, remote_version: &str) {
+        let checked_at_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.apps.insert(
+            app_name.to_string(),
+            CachedRemote {
+                remote_version: remote_version.to_string(),
+                checked_at_unix,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_app_is_not_fresh() {
+        let state = CheckState::default();
+        assert_eq!(state.fresh_remote_version("dust", 24), None);
+    }
+
+    #[test]
+    fn test_record_then_fresh() {
+        let mut state = CheckState::default();
+        state.record("dust", "1.2.3");
+        assert_eq!(state.fresh_remote_version("dust", 24), Some("1.2.3"));
+    }
+
+    #[test]
+    fn test_record_then_immediately_stale_with_zero_interval() {
+        let mut state = CheckState::default();
+        state.record("dust", "1.2.3");
+        assert_eq!(state.fresh_remote_version("dust", 0), None);
+    }
+
+    #[test]
+    fn test_unknown_fields_tolerated() {
+        let json = r#"{"apps":{"dust":{"remote_version":"1.0.0","checked_at_unix":1,"future_field":true}}}"#;
+        let state: CheckState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.apps.len(), 1);
+    }
+}